@@ -0,0 +1,100 @@
+//! Async-safe [`LRUCache`] wrapper with concurrent-request de-duplication.
+//!
+//! Requires the `async` feature.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::Mutex;
+
+use futures::future::{BoxFuture, FutureExt, Shared};
+
+use crate::LRUCache;
+
+type FetchFuture<T, E> = Shared<BoxFuture<'static, Result<T, E>>>;
+
+/// An async-safe wrapper around [`LRUCache`] that de-duplicates concurrent fetches.
+///
+/// Mirrors the request-coalescing pattern used by Proxmox's `async-lru-cache`: if multiple
+/// callers ask [`get_or_fetch`](Self::get_or_fetch) for the same missing key at once, only the
+/// first caller's fetcher future actually runs; every other caller awaits that same in-flight
+/// result instead of launching a duplicate fetch.
+pub struct AsyncLRUCache<K, T, E> {
+    cache: Mutex<LRUCache<K, T>>,
+    in_flight: Mutex<HashMap<K, FetchFuture<T, E>>>,
+}
+
+impl<K, T, E> std::fmt::Debug for AsyncLRUCache<K, T, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncLRUCache").finish_non_exhaustive()
+    }
+}
+
+impl<K, T, E> AsyncLRUCache<K, T, E>
+where
+    K: Hash + Eq + Clone,
+    T: Clone,
+    E: Clone,
+{
+    /// Create a new async cache that can hold `capacity` entries.
+    pub fn new(capacity: usize) -> Self {
+        AsyncLRUCache {
+            cache: Mutex::new(LRUCache::new(capacity)),
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the cached value for `key`, touching it if present, or awaits `fetcher` to
+    /// populate it.
+    ///
+    /// If another call for the same `key` is already in flight, this awaits that call's result
+    /// instead of polling `fetcher` at all, so concurrent callers for the same key only trigger
+    /// a single fetch.
+    pub async fn get_or_fetch<F>(&self, key: K, fetcher: F) -> Result<T, E>
+    where
+        F: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        if let Some(val) = self
+            .cache
+            .lock()
+            .expect("poisoned lock")
+            .get(&key)
+            .cloned()
+        {
+            return Ok(val);
+        }
+
+        let shared = {
+            let mut in_flight = self.in_flight.lock().expect("poisoned lock");
+            match in_flight.get(&key) {
+                Some(shared) => shared.clone(),
+                None => {
+                    let shared: FetchFuture<T, E> = fetcher.boxed().shared();
+                    in_flight.insert(key.clone(), shared.clone());
+                    shared
+                }
+            }
+        };
+
+        let result = shared.await;
+
+        // Every coalesced caller reaches this point once the shared future resolves, but only
+        // the one that still finds the entry in `in_flight` is the owner that should store the
+        // result; the others raced here after it already ran and must not re-insert.
+        let is_owner = self
+            .in_flight
+            .lock()
+            .expect("poisoned lock")
+            .remove(&key)
+            .is_some();
+
+        if let (true, Ok(val)) = (is_owner, &result) {
+            self.cache
+                .lock()
+                .expect("poisoned lock")
+                .insert(key, val.clone());
+        }
+
+        result
+    }
+}
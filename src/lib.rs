@@ -2,15 +2,84 @@
 //!
 //! `elaru` avoids all unsafe operations while still achieves O(1) performance on `insert`, `get`,
 //! and `remove_lru`. `fnv` feature is also provided for anyone looking for better performance on
-//! small key size.
+//! small key size. The `async` feature adds [`AsyncLRUCache`], a wrapper that de-duplicates
+//! concurrent fetches for the same key.
 //!
 //! See the [`LRUCache`] docs for more details.
 
 #![warn(missing_debug_implementations, missing_docs, unreachable_pub)]
 
+#[cfg(feature = "async")]
+mod async_cache;
+#[cfg(feature = "async")]
+pub use async_cache::AsyncLRUCache;
+
 #[cfg(feature = "fnv")]
 use fnv::FnvBuildHasher;
-use std::collections::{hash_map::Entry as MapEntry, HashMap};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Something that can estimate its own in-memory footprint.
+///
+/// The returned size should approximate the number of bytes the value occupies, including both
+/// its inline (stack) representation and anything it owns on the heap. This is used by
+/// [`LRUCache::with_memory_limit`] to bound a cache by total byte size rather than entry count.
+/// Implementations are provided for common standard library types; implement this trait for your
+/// own value type to use it with a memory-bounded cache.
+pub trait MemSize {
+    /// Returns an estimated byte size of `self`, including any heap allocations it owns.
+    fn mem_size(&self) -> usize;
+}
+
+macro_rules! impl_mem_size_as_size_of {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl MemSize for $t {
+                fn mem_size(&self) -> usize {
+                    std::mem::size_of::<$t>()
+                }
+            }
+        )*
+    };
+}
+
+impl_mem_size_as_size_of!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, bool, char
+);
+
+impl MemSize for String {
+    fn mem_size(&self) -> usize {
+        std::mem::size_of::<String>() + self.capacity()
+    }
+}
+
+impl MemSize for &str {
+    fn mem_size(&self) -> usize {
+        std::mem::size_of::<&str>() + self.len()
+    }
+}
+
+impl<T: MemSize> MemSize for Box<T> {
+    fn mem_size(&self) -> usize {
+        std::mem::size_of::<Box<T>>() + self.as_ref().mem_size()
+    }
+}
+
+impl<T: MemSize> MemSize for Vec<T> {
+    fn mem_size(&self) -> usize {
+        std::mem::size_of::<Vec<T>>() + self.iter().map(MemSize::mem_size).sum::<usize>()
+    }
+}
+
+impl<T: MemSize> MemSize for Option<T> {
+    fn mem_size(&self) -> usize {
+        let heap_size = match self {
+            Some(val) => val.mem_size().saturating_sub(std::mem::size_of::<T>()),
+            None => 0,
+        };
+        std::mem::size_of::<Option<T>>() + heap_size
+    }
+}
 
 /// A LRU cache builds on top of the HashMap from standard library.
 ///
@@ -22,6 +91,11 @@ use std::collections::{hash_map::Entry as MapEntry, HashMap};
 /// the cache order, so the items themselves do not need to be moved when the order changes.
 /// (This is important for speed if the items are large.)
 ///
+/// The cache key `K` can be any `Hash + Eq + Clone` type, not just a small fixed-width integer;
+/// the linked list that tracks recency links entries to each other by key instead of by a raw
+/// index, so there's no artificial limit on the number of distinct keys the cache can see over
+/// its lifetime.
+///
 /// # Example
 ///
 /// ```
@@ -42,45 +116,121 @@ use std::collections::{hash_map::Entry as MapEntry, HashMap};
 /// cache.insert(4, "Mars");
 /// assert!(cache.get(&2).is_none());
 /// ```
-#[derive(Debug, Clone)]
-pub struct LRUCache<T> {
-    /// The most-recently-used entry is at index `head`. The entries form a linked list, linked to
+pub struct LRUCache<K, T> {
+    /// The most-recently-used entry is at key `head`. The entries form a linked list, linked to
     /// each other by key within the `entries` map.
     #[cfg(not(feature = "fnv"))]
-    entries: HashMap<u16, Entry<T>>,
+    entries: HashMap<K, Entry<K, T>>,
     #[cfg(feature = "fnv")]
-    entries: HashMap<u16, Entry<T>, FnvBuildHasher>,
-    /// Index of the first entry. If the cache is empty, ignore this field.
-    head: u16,
-    /// Index of the last entry. If the cache is empty, ignore this field.
-    tail: u16,
+    entries: HashMap<K, Entry<K, T>, FnvBuildHasher>,
+    /// Key of the first entry. If the cache is empty, this is `None`.
+    head: Option<K>,
+    /// Key of the last entry. If the cache is empty, this is `None`.
+    tail: Option<K>,
+    /// Bound on [`len`](Self::len). Since keys are no longer restricted to `u16`, this carries
+    /// no `u16::MAX` overflow assertion; it is a plain entry-count limit in `usize`.
     capacity: usize,
+    /// Upper bound on `current_size`, if this cache was built with [`LRUCache::with_memory_limit`].
+    max_size: Option<usize>,
+    /// Running total of `size_of` over all stored values. Only kept up to date while `size_of`
+    /// is set, so plain count-based caches pay nothing for memory tracking.
+    current_size: usize,
+    /// `T::mem_size`, captured by [`LRUCache::with_memory_limit`]. `None` for a cache built with
+    /// [`LRUCache::new`], which never needs `T: MemSize` at all.
+    size_of: Option<fn(&T) -> usize>,
+    /// Callback registered via [`LRUCache::on_evict`], reported the key and value whenever an
+    /// entry is dropped by automatic LRU eviction.
+    ///
+    /// Bounded by `Send` only under the `async` feature, where an [`AsyncLRUCache`] holding the
+    /// cache may be shared across threads (for example by tasks started with `tokio::spawn`);
+    /// plain synchronous use has no such requirement
+    /// and can register a listener that captures `Rc`-based state.
+    #[cfg(not(feature = "async"))]
+    on_evict: Option<Box<dyn FnMut(K, T)>>,
+    #[cfg(feature = "async")]
+    on_evict: Option<Box<dyn FnMut(K, T) + Send>>,
+}
+
+impl<K, T> std::fmt::Debug for LRUCache<K, T>
+where
+    K: std::fmt::Debug + Hash + Eq + Clone,
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LRUCache")
+            .field("entries", &self.entries)
+            .field("head", &self.head)
+            .field("tail", &self.tail)
+            .field("capacity", &self.capacity)
+            .field("max_size", &self.max_size)
+            .field("current_size", &self.current_size)
+            .finish_non_exhaustive()
+    }
 }
 
 /// An entry in an LRUCache.
 #[derive(Debug, Clone)]
-pub struct Entry<T> {
+pub struct Entry<K, T> {
     val: T,
-    /// Index of the previous entry. If this entry is the head, ignore this field.
-    prev: u16,
-    /// Index of the next entry. If this entry is the tail, ignore this field.
-    next: u16,
+    /// Key of the previous entry. If this entry is the head, this is `None`.
+    prev: Option<K>,
+    /// Key of the next entry. If this entry is the tail, this is `None`.
+    next: Option<K>,
 }
 
-impl<T> LRUCache<T> {
+impl<K, T> LRUCache<K, T>
+where
+    K: Hash + Eq + Clone,
+{
     /// Create a new LRU cache that can hold `capacity` of entries.
     pub fn new(capacity: usize) -> Self {
-        let cache = LRUCache {
+        LRUCache {
             entries: HashMap::default(),
-            head: 0,
-            tail: 0,
+            head: None,
+            tail: None,
             capacity,
-        };
-        assert!(
-            cache.capacity < u16::max_value() as usize,
-            "Capacity overflow"
-        );
-        cache
+            max_size: None,
+            current_size: 0,
+            size_of: None,
+            on_evict: None,
+        }
+    }
+
+    /// Register a callback invoked with the key and value of every entry dropped by automatic
+    /// LRU eviction (from `insert`, the memory limit, or `set_capacity` shrinking the cache).
+    ///
+    /// This does not fire for entries removed by an explicit call to
+    /// [`remove_lru`](Self::remove_lru), since that call already returns the removed entry to
+    /// its caller. This is useful for write-back or tiered-cache patterns that need to flush or
+    /// persist data as it falls out of the cache.
+    #[cfg(not(feature = "async"))]
+    pub fn on_evict(&mut self, f: impl FnMut(K, T) + 'static) {
+        self.on_evict = Some(Box::new(f));
+    }
+
+    /// Register a callback invoked with the key and value of every entry dropped by automatic
+    /// LRU eviction (from `insert`, the memory limit, or `set_capacity` shrinking the cache).
+    ///
+    /// This does not fire for entries removed by an explicit call to
+    /// [`remove_lru`](Self::remove_lru), since that call already returns the removed entry to
+    /// its caller. This is useful for write-back or tiered-cache patterns that need to flush or
+    /// persist data as it falls out of the cache.
+    ///
+    /// The listener must additionally be `Send` under the `async` feature, since an
+    /// [`AsyncLRUCache`] holding the cache may be shared across threads (for example by tasks
+    /// started with `tokio::spawn`).
+    #[cfg(feature = "async")]
+    pub fn on_evict(&mut self, f: impl FnMut(K, T) + Send + 'static) {
+        self.on_evict = Some(Box::new(f));
+    }
+
+    /// Remove the least-recently-used entry, reporting it to the [`on_evict`](Self::on_evict)
+    /// listener if one is set.
+    fn evict_lru(&mut self) {
+        let (key, val) = self.remove_lru().expect("Invalid entry access");
+        if let Some(on_evict) = &mut self.on_evict {
+            on_evict(key, val);
+        }
     }
 
     /// Returns the number of elements in the cache.
@@ -88,67 +238,200 @@ impl<T> LRUCache<T> {
         self.entries.len()
     }
 
+    /// Returns `true` if the cache contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
     /// Returns the capacity of the cache.
     pub fn capacity(&self) -> usize {
         self.capacity
     }
 
+    /// Update the capacity of the cache.
+    ///
+    /// If `capacity` is smaller than [`len`](Self::len), the least-recently-used entries are
+    /// removed, oldest first, until the cache fits within the new capacity. This lets a
+    /// long-lived cache shrink under memory pressure or grow when more headroom becomes
+    /// available, without being rebuilt from scratch.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.entries.len() > capacity {
+            self.evict_lru();
+        }
+    }
+
+    /// Returns the estimated total byte size of all values currently stored, as tracked via
+    /// [`MemSize::mem_size`].
+    ///
+    /// Sizes are only tracked for a cache built with
+    /// [`with_memory_limit`](Self::with_memory_limit); a cache built with [`new`](Self::new)
+    /// always returns `0`.
+    pub fn current_size(&self) -> usize {
+        self.current_size
+    }
+
+    /// Returns the memory limit this cache was created with, if any.
+    pub fn max_size(&self) -> Option<usize> {
+        self.max_size
+    }
+
     /// Returns the entry in the list with given key.
-    pub fn get(&mut self, key: &u16) -> Option<&T> {
+    pub fn get(&mut self, key: &K) -> Option<&T> {
         if self.entries.contains_key(key) {
-            self.touch_index(*key);
+            self.touch_index(key.clone());
         }
         self.entries.get(key).map(|e| &e.val)
     }
 
     /// Returns a mutable reference to the entry in the list with given key.
-    pub fn get_mut(&mut self, key: &u16) -> Option<&mut T> {
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut T> {
         if self.entries.contains_key(key) {
-            self.touch_index(*key);
+            self.touch_index(key.clone());
         }
         self.entries.get_mut(key).map(|e| &mut e.val)
     }
 
+    /// Returns the entry in the list with given key, without marking it most-recently-used.
+    ///
+    /// Unlike [`get`](Self::get), this leaves the recency order untouched, which is useful for
+    /// metrics or eviction-policy tooling that wants to inspect a value without perturbing it.
+    pub fn peek(&self, key: &K) -> Option<&T> {
+        self.entries.get(key).map(|e| &e.val)
+    }
+
+    /// Returns the least-recently-used entry, without marking it most-recently-used.
+    ///
+    /// This is useful for seeing what [`remove_lru`](Self::remove_lru) would drop next.
+    pub fn peek_lru(&self) -> Option<(K, &T)> {
+        let key = self.tail.as_ref()?;
+        self.entries.get(key).map(|e| (key.clone(), &e.val))
+    }
+
     /// Insert a given key in the cache. Return old value if the key is present.
     ///
     /// This item becomes the front (most-recently-used) item in the cache.  If the cache is full,
-    /// the back (least-recently-used) item will be removed.
-    pub fn insert(&mut self, key: u16, val: T) -> Option<T> {
-        // If the cache is full, remove the tail entry.
-        if self.entries.len() == self.capacity {
+    /// the back (least-recently-used) item will be removed. If this cache was built with
+    /// [`with_memory_limit`](Self::with_memory_limit), the back item will also be removed,
+    /// repeatedly, until [`current_size`](Self::current_size) fits within the limit (never
+    /// evicting the entry that was just inserted).
+    pub fn insert(&mut self, key: K, val: T) -> Option<T> {
+        // If the cache is full, remove the tail entry to make room for a new key. An empty cache
+        // has nothing to evict (this is reachable with a zero capacity), so the entry just
+        // inserted is kept regardless of capacity, same as the memory-limit eviction below never
+        // evicts the entry that was just inserted. Updating an already-present key doesn't need
+        // a new slot, so it must not trigger an eviction either.
+        if self.entries.len() >= self.capacity
+            && !self.entries.is_empty()
+            && !self.entries.contains_key(&key)
+        {
             #[cfg(not(feature = "unbound"))]
-            self.remove_lru().expect("Invalid entry access");
+            self.evict_lru();
         }
 
-        let old = match self.entries.entry(key) {
-            MapEntry::Occupied(mut e) => {
-                let old_val = e.insert(Entry {
-                    val,
-                    prev: e.get().prev,
-                    next: e.get().next,
-                });
-                Some(old_val.val)
+        match self.entries.get(&key) {
+            Some(old) => {
+                let (prev, next) = (old.prev.clone(), old.next.clone());
+                if let Some(size_of) = self.size_of {
+                    self.current_size += size_of(&val);
+                    self.current_size -= size_of(&old.val);
+                }
+                let old_val = self
+                    .entries
+                    .insert(key.clone(), Entry { val, prev, next })
+                    .expect("Invalid entry access")
+                    .val;
+
+                // The entry keeps its old `prev`/`next` links above, so it must be unlinked
+                // before `push_front` re-links it at the head; otherwise a non-head entry's
+                // stale neighbors are left pointing at it, corrupting the list.
+                self.evict(key.clone());
+                self.push_front(key);
+                self.rebalance();
+                Some(old_val)
             }
-            MapEntry::Vacant(e) => {
-                e.insert(Entry {
-                    val,
-                    prev: 0,
-                    next: 0,
-                });
+            None => {
+                self.insert_vacant(key, val);
                 None
             }
-        };
+        }
+    }
 
+    /// Insert `val` under a `key` known not to already be present, linking it at the front.
+    fn insert_vacant(&mut self, key: K, val: T) {
+        if let Some(size_of) = self.size_of {
+            self.current_size += size_of(&val);
+        }
+        self.entries.insert(
+            key.clone(),
+            Entry {
+                val,
+                prev: None,
+                next: None,
+            },
+        );
         self.push_front(key);
-        old
+        self.rebalance();
+    }
+
+    /// Evict from the back until `current_size` fits `max_size`, if one is set, never evicting
+    /// the entry that was just inserted.
+    fn rebalance(&mut self) {
+        if let Some(max_size) = self.max_size {
+            while self.current_size > max_size && self.entries.len() > 1 {
+                self.evict_lru();
+            }
+        }
+    }
+
+    /// Returns the value for `key`, touching it if present, or computes it with `f`, inserts it
+    /// (subject to the usual LRU eviction), and returns a reference to the freshly stored value.
+    ///
+    /// This expresses the common "look up, compute on miss, store" flow in one call instead of a
+    /// `get` followed by an `insert`, which would hash the key twice.
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce() -> T) -> &mut T {
+        match self.try_get_or_insert_with(key, || Ok::<T, std::convert::Infallible>(f())) {
+            Ok(val) => val,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Fallible version of [`get_or_insert_with`](Self::get_or_insert_with).
+    ///
+    /// On a miss, `f` is run and its error, if any, is propagated without modifying the cache.
+    pub fn try_get_or_insert_with<E>(
+        &mut self,
+        key: K,
+        f: impl FnOnce() -> Result<T, E>,
+    ) -> Result<&mut T, E> {
+        if self.entries.contains_key(&key) {
+            self.touch_index(key.clone());
+        } else {
+            // The key is known to be vacant here, so this goes straight to `insert_vacant`
+            // instead of the public `insert`, which would re-check whether the key is present.
+            // `f` runs before any eviction so that an error leaves the cache untouched.
+            let val = f()?;
+            if self.entries.len() >= self.capacity && !self.entries.is_empty() {
+                #[cfg(not(feature = "unbound"))]
+                self.evict_lru();
+            }
+            self.insert_vacant(key.clone(), val);
+        }
+        Ok(&mut self
+            .entries
+            .get_mut(&key)
+            .expect("Invalid entry access")
+            .val)
     }
 
     /// Remove an entry from the linked list.
-    pub fn remove_lru(&mut self) -> Option<(u16, T)> {
-        self.entries.remove(&self.tail).map(|old_tail| {
-            let old_key = self.tail;
-            let new_tail = old_tail.prev;
-            self.tail = new_tail;
+    pub fn remove_lru(&mut self) -> Option<(K, T)> {
+        let old_key = self.tail.clone()?;
+        self.entries.remove(&old_key).map(|old_tail| {
+            self.tail = old_tail.prev;
+            if let Some(size_of) = self.size_of {
+                self.current_size -= size_of(&old_tail.val);
+            }
             (old_key, old_tail.val)
         })
     }
@@ -156,99 +439,174 @@ impl<T> LRUCache<T> {
     /// Clear all elements from the cache.
     pub fn clear(&mut self) {
         self.entries.clear();
+        self.current_size = 0;
     }
 
     /// Iterate over the contents of this cache.
-    pub fn iter(&self) -> Iter<T> {
+    pub fn iter(&self) -> Iter<'_, K, T> {
         Iter {
-            pos: self.head,
-            done: self.entries.len() == 0,
+            pos: self.head.clone(),
+            done: self.entries.is_empty(),
             cache: self,
         }
     }
 
     /// Touch a given entry, putting it first in the list.
     #[inline]
-    fn touch_index(&mut self, idx: u16) {
-        if idx != self.head {
-            self.evict(idx);
-            self.push_front(idx);
+    fn touch_index(&mut self, key: K) {
+        if self.head.as_ref() != Some(&key) {
+            self.evict(key.clone());
+            self.push_front(key);
         }
     }
 
     /// Evict an entry from the linked list.
     /// Note this doesn't remove the entry from the cache.
-    fn evict(&mut self, i: u16) {
-        let evicted = self.entries.get(&i).expect("Invalid entry access");
-        let prev = evicted.prev;
-        let next = evicted.next;
+    fn evict(&mut self, key: K) {
+        let evicted = self.entries.get(&key).expect("Invalid entry access");
+        let prev = evicted.prev.clone();
+        let next = evicted.next.clone();
 
-        if i == self.head {
-            self.head = next;
+        if self.head.as_ref() == Some(&key) {
+            self.head = next.clone();
         } else {
             self.entries
-                .get_mut(&prev)
+                .get_mut(prev.as_ref().expect("Invalid entry access"))
                 .expect("Invalid entry access")
-                .next = next;
+                .next = next.clone();
         }
 
-        if i == self.tail {
+        if self.tail.as_ref() == Some(&key) {
             self.tail = prev;
         } else {
             self.entries
-                .get_mut(&next)
+                .get_mut(next.as_ref().expect("Invalid entry access"))
                 .expect("Invalid entry access")
                 .prev = prev;
         }
     }
 
     /// Insert a new entry at the head of the list.
-    fn push_front(&mut self, i: u16) {
+    fn push_front(&mut self, key: K) {
         if self.entries.len() == 1 {
-            self.tail = i;
+            self.tail = Some(key.clone());
         } else {
-            self.entries.get_mut(&i).expect("Invalid entry access").next = self.head;
             self.entries
-                .get_mut(&self.head)
+                .get_mut(&key)
+                .expect("Invalid entry access")
+                .next = self.head.clone();
+            self.entries
+                .get_mut(self.head.as_ref().expect("Invalid entry access"))
                 .expect("Invalid entry access")
-                .prev = i;
+                .prev = Some(key.clone());
+        }
+        self.head = Some(key);
+    }
+}
+
+/// Memory-bounded API, available when `T` implements [`MemSize`].
+///
+/// Kept in its own `impl` block so that the count-based API above (`new`, `get`, `insert`,
+/// `iter`, ...) never requires `T: MemSize`; only a cache actually built with
+/// [`with_memory_limit`](Self::with_memory_limit) pays for size tracking.
+impl<K, T> LRUCache<K, T>
+where
+    K: Hash + Eq + Clone,
+    T: MemSize,
+{
+    /// Create a new LRU cache bounded by estimated memory usage instead of entry count.
+    ///
+    /// Entries are evicted from the back (least-recently-used first) whenever
+    /// [`current_size`](Self::current_size) would exceed `max_bytes` after an insert, as
+    /// estimated by each value's [`MemSize::mem_size`]. The entry that was just inserted is
+    /// never evicted to make room for itself.
+    pub fn with_memory_limit(max_bytes: usize) -> Self {
+        LRUCache {
+            entries: HashMap::default(),
+            head: None,
+            tail: None,
+            capacity: usize::MAX,
+            max_size: Some(max_bytes),
+            current_size: 0,
+            size_of: Some(T::mem_size),
+            on_evict: None,
+        }
+    }
+
+    /// Apply `f` to the value behind `key` without handing out a raw `&mut T`.
+    ///
+    /// This touches the entry like [`get_mut`](Self::get_mut), but also re-measures the value's
+    /// [`MemSize::mem_size`] after `f` runs and rebalances against the memory limit, since
+    /// mutating a value in place can change how much memory it occupies. Returns `false` if
+    /// `key` is not present.
+    pub fn mutate(&mut self, key: &K, f: impl FnOnce(&mut T)) -> bool {
+        if !self.entries.contains_key(key) {
+            return false;
+        }
+        self.touch_index(key.clone());
+
+        let entry = self.entries.get_mut(key).expect("Invalid entry access");
+        if self.size_of.is_some() {
+            let old_size = entry.val.mem_size();
+            f(&mut entry.val);
+            let new_size = entry.val.mem_size();
+            self.current_size = self.current_size - old_size + new_size;
+        } else {
+            f(&mut entry.val);
+        }
+
+        if let Some(max_size) = self.max_size {
+            while self.current_size > max_size && self.entries.len() > 1 {
+                self.evict_lru();
+            }
         }
-        self.head = i;
+        true
     }
 }
 
-/// Mutable iterator over values in an LRUCache, from most-recently-used to least-recently-used.
-#[derive(Debug)]
-pub struct Iter<'a, T> {
-    cache: &'a LRUCache<T>,
-    pos: u16,
+/// Iterator over values in an LRUCache, from most-recently-used to least-recently-used.
+pub struct Iter<'a, K, T> {
+    cache: &'a LRUCache<K, T>,
+    pos: Option<K>,
     done: bool,
 }
 
-impl<'a, T> Iterator for Iter<'a, T>
+impl<'a, K, T> std::fmt::Debug for Iter<'a, K, T>
+where
+    K: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Iter")
+            .field("pos", &self.pos)
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, K, T> Iterator for Iter<'a, K, T>
 where
+    K: Hash + Eq + Clone + 'a,
     T: 'a,
 {
-    type Item = (u16, &'a T);
+    type Item = (K, &'a T);
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.done {
             return None;
         }
 
-        // Use a raw pointer because the compiler doesn't know that subsequent calls can't alias.
-        //let entry = unsafe { &mut *(&mut self.cache.entries[self.pos as usize] as *mut Entry<T>) };
+        let pos = self.pos.clone().expect("Invalid entry access");
         let (key, entry) = self
             .cache
             .entries
-            .get_key_value(&self.pos)
+            .get_key_value(&pos)
             .expect("Invalid entry access");
 
         if self.pos == self.cache.tail {
             self.done = true;
         }
-        self.pos = entry.next;
+        self.pos = entry.next.clone();
 
-        Some((*key, &entry.val))
+        Some((key.clone(), &entry.val))
     }
 }
@@ -0,0 +1,60 @@
+#![cfg(feature = "async")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use elaru::AsyncLRUCache;
+
+#[tokio::test]
+async fn get_or_fetch_dedups_concurrent_requests() {
+    let cache: Arc<AsyncLRUCache<u16, i32, &'static str>> = Arc::new(AsyncLRUCache::new(4));
+    let fetch_count = Arc::new(AtomicUsize::new(0));
+
+    let mut handles = Vec::new();
+    for _ in 0..8 {
+        let cache = cache.clone();
+        let fetch_count = fetch_count.clone();
+        handles.push(tokio::spawn(async move {
+            cache
+                .get_or_fetch(1, async move {
+                    fetch_count.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    Ok(42)
+                })
+                .await
+        }));
+    }
+
+    for handle in handles {
+        assert_eq!(handle.await.unwrap(), Ok(42));
+    }
+    assert_eq!(
+        fetch_count.load(Ordering::SeqCst),
+        1,
+        "only the first caller's fetcher should run"
+    );
+
+    // A later call for the same key hits the now-populated cache without fetching again.
+    let hit = cache
+        .get_or_fetch(1, async { panic!("should not fetch again") })
+        .await;
+    assert_eq!(hit, Ok(42));
+
+    // Each coalesced awaiter resolving the shared future should not re-insert the key; with the
+    // cache's capacity of 4 and only one key stored so far, a second distinct key must not evict
+    // the first, which redundant re-inserts that corrupted the list could cause.
+    let hit2 = cache.get_or_fetch(2, async { Ok(7) }).await;
+    assert_eq!(hit2, Ok(7));
+    let hit = cache
+        .get_or_fetch(1, async { panic!("should not fetch again") })
+        .await;
+    assert_eq!(hit, Ok(42), "first key survives a second key being cached");
+}
+
+#[tokio::test]
+async fn get_or_fetch_propagates_errors() {
+    let cache: AsyncLRUCache<u16, i32, &'static str> = AsyncLRUCache::new(4);
+    let result = cache.get_or_fetch(1, async { Err("boom") }).await;
+    assert_eq!(result, Err("boom"));
+}
@@ -1,8 +1,9 @@
 use elaru::*;
 
 /// Convenience function for test assertions
-fn items<T>(cache: &LRUCache<T>) -> Vec<u16>
+fn items<K, T>(cache: &LRUCache<K, T>) -> Vec<K>
 where
+    K: std::hash::Hash + Eq + Clone,
     T: Clone,
 {
     cache.iter().map(|(x, _)| x.clone()).collect()
@@ -10,9 +11,9 @@ where
 
 #[test]
 fn empty() {
-    let mut cache: LRUCache<u8> = LRUCache::new(4);
+    let cache: LRUCache<u16, u8> = LRUCache::new(4);
     assert_eq!(cache.len(), 0);
-    assert_eq!(items(&mut cache), []);
+    assert_eq!(items(&cache), []);
 }
 
 #[test]
@@ -35,7 +36,7 @@ fn insert() {
     dbg!(&cache);
     assert_eq!(cache.len(), 4);
     assert_eq!(
-        items(&mut cache),
+        items(&cache),
         [5, 4, 3, 2],
         "Least-recently-used item cleared."
     );
@@ -45,12 +46,58 @@ fn insert() {
     cache.insert(8, "h");
     cache.insert(9, "i");
     assert_eq!(
-        items(&mut cache),
+        items(&cache),
         [9, 8, 7, 6],
         "Least-recently-used item cleared."
     );
 }
 
+#[test]
+fn insert_existing_key_relinks() {
+    let mut cache = LRUCache::new(4);
+    cache.insert(1, "a");
+    cache.insert(2, "b");
+    cache.insert(3, "c");
+
+    // Re-inserting a non-head key must unlink it from its old position before moving it to the
+    // front, or the list ends up corrupted (entries `iter` silently drops while `len` still
+    // counts them).
+    cache.insert(2, "bb");
+    assert_eq!(cache.len(), 3);
+    assert_eq!(
+        items(&cache),
+        [2, 3, 1],
+        "Re-inserted key moves to the front."
+    );
+
+    cache.insert(1, "aa");
+    assert_eq!(cache.len(), 3);
+    assert_eq!(
+        items(&cache),
+        [1, 2, 3],
+        "Re-inserting the tail also relinks it correctly."
+    );
+}
+
+#[test]
+fn insert_existing_key_on_full_cache() {
+    let mut cache = LRUCache::new(2);
+    cache.insert(1, 100);
+    cache.insert(2, 200);
+
+    // Updating an already-present key on a full cache must not evict anything else to make
+    // room, since no new slot is needed, and must return the old value.
+    let old = cache.insert(2, 222);
+    assert_eq!(old, Some(200), "update returns the replaced value");
+    assert_eq!(cache.len(), 2, "no entry is evicted for an in-place update");
+    assert_eq!(items(&cache), [2, 1], "updated key moves to the front");
+
+    let old = cache.insert(1, 111);
+    assert_eq!(old, Some(100));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(items(&cache), [1, 2]);
+}
+
 #[test]
 fn lookup() {
     let mut cache = LRUCache::new(4);
@@ -61,13 +108,13 @@ fn lookup() {
 
     let result = cache.get(&5);
     assert_eq!(result, None, "Cache miss.");
-    assert_eq!(items(&mut cache), [4, 3, 2, 1], "Order not changed.");
+    assert_eq!(items(&cache), [4, 3, 2, 1], "Order not changed.");
 
     // Cache hit
     let result = cache.get_mut(&3);
     assert_eq!(result, Some(&mut 300), "Cache hit.");
     assert_eq!(
-        items(&mut cache),
+        items(&cache),
         [3, 4, 2, 1],
         "Matching item moved to front."
     );
@@ -78,15 +125,15 @@ fn clear() {
     let mut cache = LRUCache::new(4);
     cache.insert(1, 100);
     cache.clear();
-    assert_eq!(items(&mut cache), [], "all items cleared");
+    assert_eq!(items(&cache), [], "all items cleared");
 
     cache.insert(1, 100);
     cache.insert(2, 200);
     cache.insert(3, 300);
     cache.insert(4, 400);
-    assert_eq!(items(&mut cache), [4, 3, 2, 1]);
+    assert_eq!(items(&cache), [4, 3, 2, 1]);
     cache.clear();
-    assert_eq!(items(&mut cache), [], "all items cleared again");
+    assert_eq!(items(&cache), [], "all items cleared again");
 }
 
 #[test]
@@ -99,12 +146,195 @@ fn remove_lru() {
     cache.insert(4, 400);
     cache.remove_lru();
     assert_eq!(
-        items(&mut cache),
+        items(&cache),
         [4, 3, 2],
         "Least-recently-used item cleared."
     );
 }
 
+#[test]
+fn peek() {
+    let mut cache = LRUCache::new(4);
+    cache.insert(1, 100);
+    cache.insert(2, 200);
+    cache.insert(3, 300);
+
+    assert_eq!(cache.peek(&1), Some(&100), "Cache hit.");
+    assert_eq!(cache.peek(&4), None, "Cache miss.");
+    assert_eq!(
+        items(&cache),
+        [3, 2, 1],
+        "peek does not reorder the cache."
+    );
+
+    assert_eq!(cache.peek_lru(), Some((1, &100)));
+    assert_eq!(
+        items(&cache),
+        [3, 2, 1],
+        "peek_lru does not reorder the cache."
+    );
+}
+
+#[test]
+fn get_or_insert_with() {
+    let mut cache = LRUCache::new(4);
+    cache.insert(1, 100);
+
+    let mut computed = false;
+    let val = cache.get_or_insert_with(1, || {
+        computed = true;
+        999
+    });
+    assert_eq!(val, &100, "Cache hit returns the existing value.");
+    assert!(!computed, "f is not called on a hit.");
+
+    let val = cache.get_or_insert_with(2, || {
+        computed = true;
+        200
+    });
+    assert_eq!(val, &200, "Cache miss inserts the computed value.");
+    assert!(computed, "f is called on a miss.");
+    assert_eq!(cache.peek(&2), Some(&200));
+}
+
+#[test]
+fn try_get_or_insert_with() {
+    let mut cache: LRUCache<u16, i32> = LRUCache::new(4);
+
+    let err = cache.try_get_or_insert_with(1, || Err("boom"));
+    assert_eq!(err, Err("boom"), "Error from f is propagated.");
+    assert_eq!(cache.peek(&1), None, "Nothing is inserted on error.");
+
+    let val = cache.try_get_or_insert_with(1, || Ok::<i32, &str>(100));
+    assert_eq!(val, Ok(&mut 100));
+    assert_eq!(cache.peek(&1), Some(&100));
+}
+
+// Outside the `async` feature, `on_evict`'s listener is only `'static`, not `Send`, so it can
+// capture non-`Send` state like `Rc`/`RefCell`.
+#[cfg(not(feature = "async"))]
+#[test]
+fn on_evict() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let evicted = Rc::new(RefCell::new(Vec::new()));
+
+    let mut cache = LRUCache::new(2);
+    let sink = evicted.clone();
+    cache.on_evict(move |k, v| sink.borrow_mut().push((k, v)));
+
+    cache.insert(1, 100);
+    cache.insert(2, 200);
+    assert!(
+        evicted.borrow().is_empty(),
+        "no eviction while under capacity"
+    );
+
+    cache.insert(3, 300);
+    assert_eq!(*evicted.borrow(), [(1, 100)], "oldest entry reported");
+
+    // remove_lru already returns the entry to its caller, so it should not also notify.
+    cache.remove_lru();
+    assert_eq!(
+        *evicted.borrow(),
+        [(1, 100)],
+        "remove_lru does not trigger the listener"
+    );
+}
+
+// Under the `async` feature, the listener must be `Send` so `AsyncLRUCache` can share the cache
+// across threads.
+#[cfg(feature = "async")]
+#[test]
+fn on_evict() {
+    use std::sync::{Arc, Mutex};
+
+    let evicted = Arc::new(Mutex::new(Vec::new()));
+
+    let mut cache = LRUCache::new(2);
+    let sink = evicted.clone();
+    cache.on_evict(move |k, v| sink.lock().unwrap().push((k, v)));
+
+    cache.insert(1, 100);
+    cache.insert(2, 200);
+    assert!(
+        evicted.lock().unwrap().is_empty(),
+        "no eviction while under capacity"
+    );
+
+    cache.insert(3, 300);
+    assert_eq!(*evicted.lock().unwrap(), [(1, 100)], "oldest entry reported");
+
+    // remove_lru already returns the entry to its caller, so it should not also notify.
+    cache.remove_lru();
+    assert_eq!(
+        *evicted.lock().unwrap(),
+        [(1, 100)],
+        "remove_lru does not trigger the listener"
+    );
+}
+
+#[test]
+fn set_capacity() {
+    let mut cache = LRUCache::new(4);
+    cache.insert(1, 100);
+    cache.insert(2, 200);
+    cache.insert(3, 300);
+    cache.insert(4, 400);
+
+    cache.set_capacity(2);
+    assert_eq!(cache.capacity(), 2);
+    assert_eq!(
+        items(&cache),
+        [4, 3],
+        "Shrinking drops least-recently-used entries first."
+    );
+
+    cache.set_capacity(4);
+    cache.insert(5, 500);
+    cache.insert(6, 600);
+    assert_eq!(
+        items(&cache),
+        [6, 5, 4, 3],
+        "Growing allows more entries again."
+    );
+}
+
+#[test]
+fn set_capacity_zero_then_insert() {
+    let mut cache = LRUCache::new(4);
+    cache.insert(1, 100);
+
+    cache.set_capacity(0);
+    assert_eq!(cache.len(), 0, "shrinking to zero empties the cache");
+
+    cache.insert(2, 200);
+    assert_eq!(
+        cache.peek(&2),
+        Some(&200),
+        "inserting into a zero-capacity cache does not panic"
+    );
+}
+
+#[test]
+fn memory_limit() {
+    // Each `i32` value costs 4 bytes, so a budget of 12 bytes holds 3 of them.
+    let mut cache = LRUCache::with_memory_limit(12);
+    cache.insert(1, 100);
+    cache.insert(2, 200);
+    cache.insert(3, 300);
+    assert_eq!(cache.current_size(), 12);
+
+    cache.insert(4, 400);
+    assert_eq!(cache.current_size(), 12, "oldest entry evicted to fit");
+    assert_eq!(items(&cache), [4, 3, 2]);
+
+    cache.mutate(&4, |v| *v = 999);
+    assert_eq!(cache.get(&4), Some(&999));
+    assert_eq!(cache.current_size(), 12, "mutation kept the same size");
+}
+
 #[test]
 fn iter() {
     let mut cache = LRUCache::new(4);